@@ -0,0 +1,125 @@
+use std::fs::File;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// Archive formats used by the emmylua_ls release assets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ArchiveKind {
+  GzipTar,
+  Zip,
+}
+
+impl ArchiveKind {
+  pub fn from_name(name: &str) -> Self {
+    if name.ends_with(".zip") {
+      Self::Zip
+    } else {
+      Self::GzipTar
+    }
+  }
+
+  /// Kind of the archive at `path`, going by its magic bytes so a misnamed mirror asset still
+  /// extracts. Falls back to `name` when the content isn't recognized.
+  pub fn from_file(path: &Path, name: &str) -> Self {
+    let mut magic = [0u8; 4];
+    if File::open(path)
+      .and_then(|mut file| io::Read::read_exact(&mut file, &mut magic))
+      .is_err()
+    {
+      return Self::from_name(name);
+    }
+
+    match magic {
+      [b'P', b'K', 3, 4] => Self::Zip,
+      [0x1f, 0x8b, _, _] => Self::GzipTar,
+      _ => Self::from_name(name),
+    }
+  }
+}
+
+/// Lowercase hex SHA-256 digest of the file at `path`.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+  let mut file = File::open(path).map_err(|e| e.to_string())?;
+  let mut hasher = Sha256::new();
+  io::copy(&mut file, &mut hasher).map_err(|e| e.to_string())?;
+
+  Ok(
+    hasher
+      .finalize()
+      .iter()
+      .map(|byte| format!("{byte:02x}"))
+      .collect(),
+  )
+}
+
+/// Extracts `archive` into `destination`, creating the directory if needed.
+pub fn extract(archive: &Path, kind: ArchiveKind, destination: &Path) -> Result<(), String> {
+  let result = File::open(archive).and_then(|file| {
+    std::fs::create_dir_all(destination)?;
+    match kind {
+      ArchiveKind::GzipTar => extract_tar_gz(file, destination),
+      ArchiveKind::Zip => extract_zip(file, destination),
+    }
+  });
+
+  result.map_err(|e| format!("Failed to extract {}: {}", archive.display(), e))
+}
+
+fn extract_tar_gz(file: File, destination: &Path) -> io::Result<()> {
+  let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(file));
+
+  for entry in archive.entries()? {
+    let mut entry = entry?;
+    let Some(target) = enclosed_path(destination, &entry.path()?) else {
+      continue;
+    };
+
+    let entry_type = entry.header().entry_type();
+    if entry_type.is_dir() {
+      std::fs::create_dir_all(&target)?;
+    } else if entry_type.is_file() {
+      write_entry(&mut entry, &target)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn extract_zip(file: File, destination: &Path) -> io::Result<()> {
+  let mut archive = zip::ZipArchive::new(file).map_err(io::Error::other)?;
+
+  for index in 0..archive.len() {
+    let mut entry = archive.by_index(index).map_err(io::Error::other)?;
+    let Some(target) = entry.enclosed_name().map(|name| destination.join(name)) else {
+      continue;
+    };
+
+    if entry.is_dir() {
+      std::fs::create_dir_all(&target)?;
+    } else {
+      write_entry(&mut entry, &target)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn write_entry(reader: &mut impl io::Read, target: &Path) -> io::Result<()> {
+  if let Some(parent) = target.parent() {
+    std::fs::create_dir_all(parent)?;
+  }
+
+  let mut output = File::create(target)?;
+  io::copy(reader, &mut output)?;
+  Ok(())
+}
+
+/// Joins an archive entry path onto `destination`, rejecting absolute paths and `..` components.
+fn enclosed_path(destination: &Path, entry: &Path) -> Option<PathBuf> {
+  entry
+    .components()
+    .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    .then(|| destination.join(entry))
+}
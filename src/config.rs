@@ -0,0 +1,1439 @@
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use zed_extension_api::serde_json::{self, Map, Value};
+
+/// Top-level settings keys consumed by the extension itself and never sent to the server.
+const EXTENSION_KEYS: [&str; 1] = ["server"];
+
+/// Sections emmylua_ls only understands from the given release on. Older servers log a warning
+/// for every unknown section, so these are left out unless the user set them.
+const SECTION_MIN_VERSIONS: [(&str, &str); 2] =
+  [("documentColor", "0.9.0"), ("inlineValues", "0.9.0")];
+
+/// Oldest emmylua_ls release the generated configuration is meant for. Older servers reject some
+/// of the keys the extension sends.
+pub const MIN_SERVER_VERSION: &str = "0.7.0";
+
+/// Shortest `workspace.reindexDuration` and `diagnostics.diagnosticInterval`, in milliseconds.
+/// Smaller values make the server reindex or re-diagnose almost continuously.
+const MIN_INTERVAL_MS: u64 = 100;
+
+/// Range of `hover.customDetail` accepted by emmylua_ls.
+const HOVER_CUSTOM_DETAIL_RANGE: std::ops::RangeInclusive<u64> = 1..=255;
+
+/// Configuration sent to emmylua_ls, mirroring the sections of `.emmyrc.json`.
+///
+/// Every section keeps the keys it doesn't model in `extra`, so settings added by newer server
+/// versions are forwarded as they are instead of being dropped.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EmmyConfig {
+  pub workspace: Workspace,
+  pub completion: Completion,
+  pub diagnostics: Diagnostics,
+  pub hint: Hint,
+  pub runtime: Runtime,
+  pub hover: Hover,
+  pub format: Format,
+  pub doc: Doc,
+  pub code_lens: CodeLens,
+  pub semantic_tokens: SemanticTokens,
+  pub signature: Signature,
+  pub references: References,
+  pub document_color: DocumentColor,
+  pub inline_values: InlineValues,
+  pub code_action: CodeAction,
+  pub strict: Strict,
+  pub resource: Resource,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl EmmyConfig {
+  /// Turns off the named sections' `enable` flag, for `server.disabledFeatures`. Names are the
+  /// section names, e.g. `codeLens`.
+  pub fn disable_features(&mut self, features: &[String]) {
+    for feature in features {
+      let enable = match feature.trim() {
+        "completion" => &mut self.completion.enable,
+        "diagnostics" => &mut self.diagnostics.enable,
+        "hint" => &mut self.hint.enable,
+        "hover" => &mut self.hover.enable,
+        "codeLens" => &mut self.code_lens.enable,
+        "semanticTokens" => &mut self.semantic_tokens.enable,
+        "references" => &mut self.references.enable,
+        "documentColor" => &mut self.document_color.enable,
+        "inlineValues" => &mut self.inline_values.enable,
+        _ => {
+          warn!(
+            "ignoring unknown feature '{feature}' in server.disabledFeatures, expected one of \
+             completion, diagnostics, hint, hover, codeLens, semanticTokens, references, \
+             documentColor or inlineValues"
+          );
+          continue;
+        }
+      };
+      *enable = false;
+    }
+  }
+
+  /// Drops every key the extension doesn't model, so nothing but the known settings reaches the
+  /// server. Used for `server.strictSettings`.
+  pub fn drop_unknown_keys(&mut self) {
+    let extras = [
+      ("", &mut self.extra),
+      ("workspace", &mut self.workspace.extra),
+      ("completion", &mut self.completion.extra),
+      ("diagnostics", &mut self.diagnostics.extra),
+      ("hint", &mut self.hint.extra),
+      ("runtime", &mut self.runtime.extra),
+      ("hover", &mut self.hover.extra),
+      ("format", &mut self.format.extra),
+      ("doc", &mut self.doc.extra),
+      ("codeLens", &mut self.code_lens.extra),
+      ("semanticTokens", &mut self.semantic_tokens.extra),
+      ("signature", &mut self.signature.extra),
+      ("references", &mut self.references.extra),
+      ("documentColor", &mut self.document_color.extra),
+      ("inlineValues", &mut self.inline_values.extra),
+      ("codeAction", &mut self.code_action.extra),
+      ("strict", &mut self.strict.extra),
+      ("resource", &mut self.resource.extra),
+    ];
+    for (section, extra) in extras {
+      for key in extra.keys() {
+        match section {
+          "" => warn!("server.strictSettings: not sending unknown section {key}"),
+          _ => warn!("server.strictSettings: not sending unknown setting {section}.{key}"),
+        }
+      }
+      extra.clear();
+    }
+  }
+
+  /// Builds the configuration from the LSP settings, falling back to the defaults for anything
+  /// missing or invalid.
+  pub fn from_settings(settings: &Value) -> Self {
+    let mut config = Self {
+      workspace: section(settings, "workspace"),
+      completion: section(settings, "completion"),
+      diagnostics: section(settings, "diagnostics"),
+      hint: section(settings, "hint"),
+      runtime: section(settings, "runtime"),
+      hover: section(settings, "hover"),
+      format: section(settings, "format"),
+      doc: section(settings, "doc"),
+      code_lens: section(settings, "codeLens"),
+      semantic_tokens: section(settings, "semanticTokens"),
+      signature: section(settings, "signature"),
+      references: section(settings, "references"),
+      document_color: section(settings, "documentColor"),
+      inline_values: section(settings, "inlineValues"),
+      code_action: section(settings, "codeAction"),
+      strict: section(settings, "strict"),
+      resource: section(settings, "resource"),
+      extra: Map::new(),
+    };
+
+    // Whole sections the extension doesn't know about yet
+    if let (Some(settings), Ok(Value::Object(known))) =
+      (settings.as_object(), serde_json::to_value(&config))
+    {
+      config.extra = settings
+        .iter()
+        .filter(|(key, _)| !known.contains_key(*key) && !EXTENSION_KEYS.contains(&key.as_str()))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect();
+    }
+
+    config.runtime.version = match normalize_runtime_version(&config.runtime.version) {
+      Some(version) => version.to_string(),
+      None => {
+        warn!(
+          "unknown runtime.version '{}', using LuaLatest",
+          config.runtime.version
+        );
+        "LuaLatest".to_string()
+      }
+    };
+
+    if config.completion.postfix.chars().count() > 1 {
+      warn!(
+        "completion.postfix '{}' must be a single character, using '@'",
+        config.completion.postfix
+      );
+      config.completion.postfix = "@".to_string();
+    }
+
+    for (name, tool) in [
+      ("format.externalTool", &mut config.format.external_tool),
+      (
+        "format.externalToolRangeFormat",
+        &mut config.format.external_tool_range_format,
+      ),
+    ] {
+      if tool
+        .as_ref()
+        .is_some_and(|tool| tool.program.trim().is_empty())
+      {
+        warn!("ignoring {name}: program must not be empty");
+        *tool = None;
+      }
+    }
+
+    let syntax = config.doc.syntax.trim().to_ascii_lowercase();
+    config.doc.syntax = match syntax.as_str() {
+      "md" | "myst" | "rst" | "none" => syntax,
+      _ => {
+        warn!("unknown doc.syntax '{}', using md", config.doc.syntax);
+        "md".to_string()
+      }
+    };
+    // The reStructuredText roles only mean something to the rst and MyST renderers
+    let uses_roles = matches!(config.doc.syntax.as_str(), "rst" | "myst");
+    if !uses_roles
+      && (config.doc.rst_default_role.is_some() || config.doc.rst_primary_domain.is_some())
+    {
+      warn!(
+        "doc.rstDefaultRole and doc.rstPrimaryDomain are ignored with doc.syntax '{}'",
+        config.doc.syntax
+      );
+      config.doc.rst_default_role = None;
+      config.doc.rst_primary_domain = None;
+    } else if config.doc.syntax == "rst" && config.doc.rst_primary_domain.is_none() {
+      warn!("doc.syntax is rst without doc.rstPrimaryDomain, using the server default");
+    }
+
+    let mut functions: Vec<String> = Vec::new();
+    for function in std::mem::take(&mut config.completion.auto_require_function) {
+      let function = function.trim();
+      if function.is_empty() {
+        warn!("ignoring empty completion.autoRequireFunction entry");
+      } else if !functions.iter().any(|known| known == function) {
+        functions.push(function.to_string());
+      }
+    }
+    if functions.is_empty() {
+      functions.push("require".to_string());
+    }
+    for function in functions.drain(1..) {
+      if !config.runtime.require_like_function.contains(&function) {
+        config.runtime.require_like_function.push(function);
+      }
+    }
+    config.completion.auto_require_function = functions;
+
+    let separator = config.completion.auto_require_separator.trim();
+    if separator != "." && separator != "/" {
+      warn!("completion.autoRequireSeparator '{separator}' must be '.' or '/', using '.'");
+      config.completion.auto_require_separator = ".".to_string();
+    } else {
+      config.completion.auto_require_separator = separator.to_string();
+    }
+
+    let convention = &config.completion.auto_require_naming_convention;
+    config.completion.auto_require_naming_convention = match normalize_naming_convention(convention)
+    {
+      Some(convention) => convention.to_string(),
+      None => {
+        warn!("unknown completion.autoRequireNamingConvention '{convention}', using keep");
+        "keep".to_string()
+      }
+    };
+
+    // Negative values don't deserialize and already fall back to the defaults
+    config.workspace.reindex_duration = config
+      .workspace
+      .reindex_duration
+      .filter(|_| config.workspace.enable_reindex)
+      .map(|duration| clamp_interval("workspace.reindexDuration", duration));
+    config.diagnostics.diagnostic_interval = clamp_interval(
+      "diagnostics.diagnosticInterval",
+      config.diagnostics.diagnostic_interval,
+    );
+
+    config.diagnostics.severity = std::mem::take(&mut config.diagnostics.severity)
+      .into_iter()
+      .filter_map(
+        |(code, severity)| match severity.as_str().and_then(normalize_severity) {
+          Some(normalized) => Some((code, Value::from(normalized))),
+          None => {
+            warn!("ignoring diagnostics.severity.{code}: {severity} is not a valid severity");
+            None
+          }
+        },
+      )
+      .collect();
+
+    let mut framework_versions = Vec::new();
+    for entry in std::mem::take(&mut config.runtime.framework_versions) {
+      let entry = entry.trim();
+      if entry.is_empty() {
+        warn!("ignoring empty runtime.frameworkVersions entry");
+      } else if !framework_versions.iter().any(|known| known == entry) {
+        framework_versions.push(entry.to_string());
+      }
+    }
+    config.runtime.framework_versions = framework_versions;
+
+    if let Some(detail) = config.hover.custom_detail
+      && !HOVER_CUSTOM_DETAIL_RANGE.contains(&detail)
+    {
+      let clamped = detail.clamp(
+        *HOVER_CUSTOM_DETAIL_RANGE.start(),
+        *HOVER_CUSTOM_DETAIL_RANGE.end(),
+      );
+      warn!(
+        "hover.customDetail {detail} is outside {}..={}, using {clamped}",
+        HOVER_CUSTOM_DETAIL_RANGE.start(),
+        HOVER_CUSTOM_DETAIL_RANGE.end()
+      );
+      config.hover.custom_detail = Some(clamped);
+    }
+
+    config
+  }
+}
+
+/// Removes sections from the serialized `config` that `server_version` predates, unless `settings`
+/// sets them explicitly. Unparseable versions keep everything.
+pub fn drop_unsupported_sections(config: &mut Value, settings: &Value, server_version: &str) {
+  let Some(config) = config.as_object_mut() else {
+    return;
+  };
+
+  for (section, min_version) in SECTION_MIN_VERSIONS {
+    if !version_at_least(server_version, min_version) && settings.get(section).is_none() {
+      config.remove(section);
+    }
+  }
+}
+
+/// Expands `strict.preset` into the individual `strict.*` flags. Flags set explicitly next to the
+/// preset keep their value, and the preset itself is never sent to the server.
+pub fn expand_strict_preset(settings: &mut Value) {
+  let Some(strict) = settings.get_mut("strict").and_then(Value::as_object_mut) else {
+    return;
+  };
+  let Some(preset) = strict.remove("preset") else {
+    return;
+  };
+
+  // arrayIndex, docBaseConstMatchBaseType, metaOverrideFileDefine, requirePath, typeCall
+  let flags = match preset
+    .as_str()
+    .map(|preset| preset.trim().to_ascii_lowercase())
+  {
+    Some(preset) if preset == "off" => [false; 5],
+    Some(preset) if preset == "relaxed" => [true, false, true, false, false],
+    Some(preset) if preset == "strict" => [true; 5],
+    _ => {
+      warn!("unknown strict.preset {preset}, expected off, relaxed or strict");
+      return;
+    }
+  };
+  let keys = [
+    "arrayIndex",
+    "docBaseConstMatchBaseType",
+    "metaOverrideFileDefine",
+    "requirePath",
+    "typeCall",
+  ];
+  for (key, flag) in keys.into_iter().zip(flags) {
+    strict.entry(key).or_insert(Value::Bool(flag));
+  }
+}
+
+/// Removes the named top-level sections from the serialized `config`, for `server.omitSections`.
+pub fn omit_sections(config: &mut Value, sections: &[String]) {
+  let Some(config) = config.as_object_mut() else {
+    return;
+  };
+
+  // Known sections may already be missing, e.g. when the server version predates them
+  let known = serde_json::to_value(EmmyConfig::default()).unwrap_or_default();
+  for section in sections {
+    if config.remove(section).is_none() && known.get(section).is_none() {
+      warn!("server.omitSections: there is no section '{section}' to omit");
+    }
+  }
+}
+
+/// Whether release tag `version` is `floor` or newer. A pre-release such as `0.9.0-beta` comes
+/// before `0.9.0` itself. Unparseable versions count as new enough.
+pub fn version_at_least(version: &str, floor: &str) -> bool {
+  let (Some(parsed), Some(parsed_floor)) = (parse_version(version), parse_version(floor)) else {
+    return true;
+  };
+
+  let is_prerelease = |version: &str| version.trim().contains('-');
+  match parsed.cmp(&parsed_floor) {
+    std::cmp::Ordering::Greater => true,
+    std::cmp::Ordering::Less => false,
+    std::cmp::Ordering::Equal => !is_prerelease(version) || is_prerelease(floor),
+  }
+}
+
+/// Parses release tags such as `0.9.1` or `v0.10.0-beta` into their numeric components.
+fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+  let mut parts = version
+    .trim()
+    .trim_start_matches('v')
+    .splitn(3, '.')
+    .map(|part| {
+      part
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>()
+        .parse::<u64>()
+        .ok()
+    });
+
+  Some((
+    parts.next()??,
+    parts.next().flatten().unwrap_or(0),
+    parts.next().flatten().unwrap_or(0),
+  ))
+}
+
+/// Turns JSON with comments and trailing commas, as editors write `.emmyrc.json` and
+/// `.luarc.json`, into plain JSON. Comments and dropped commas become spaces, so line and column
+/// numbers in parse errors still point into the original file.
+pub fn strip_jsonc(content: &str) -> String {
+  let mut stripped = String::with_capacity(content.len());
+  let mut chars = content.chars().peekable();
+  let blank = |stripped: &mut String, c: char| match c {
+    '\n' => stripped.push('\n'),
+    c => stripped.extend(std::iter::repeat_n(' ', c.len_utf8())),
+  };
+
+  while let Some(c) = chars.next() {
+    match c {
+      '"' => {
+        stripped.push(c);
+        let mut escaped = false;
+        for c in chars.by_ref() {
+          stripped.push(c);
+          match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => break,
+            _ => {}
+          }
+        }
+      }
+      '/' if chars.peek() == Some(&'/') => {
+        blank(&mut stripped, c);
+        while let Some(c) = chars.next_if(|&c| c != '\n') {
+          blank(&mut stripped, c);
+        }
+      }
+      '/' if chars.peek() == Some(&'*') => {
+        blank(&mut stripped, c);
+        blank(&mut stripped, chars.next().unwrap_or('*'));
+        while let Some(c) = chars.next() {
+          blank(&mut stripped, c);
+          if c == '*' && chars.peek() == Some(&'/') {
+            blank(&mut stripped, chars.next().unwrap_or('/'));
+            break;
+          }
+        }
+      }
+      c => stripped.push(c),
+    }
+  }
+
+  // With the comments gone a comma is trailing when only whitespace separates it from a bracket
+  let bytes = stripped.as_bytes();
+  let mut trailing = Vec::new();
+  let mut in_string = false;
+  let mut escaped = false;
+  for (i, &byte) in bytes.iter().enumerate() {
+    if in_string {
+      match byte {
+        _ if escaped => escaped = false,
+        b'\\' => escaped = true,
+        b'"' => in_string = false,
+        _ => {}
+      }
+    } else if byte == b'"' {
+      in_string = true;
+    } else if byte == b','
+      && matches!(
+        bytes[i + 1..].iter().find(|b| !b.is_ascii_whitespace()),
+        Some(b'}' | b']')
+      )
+    {
+      trailing.push(i);
+    }
+  }
+  for i in trailing {
+    stripped.replace_range(i..=i, " ");
+  }
+
+  stripped
+}
+
+/// Parses a `diagnostics.globalsFile`, either a JSON array of names or one name per line. Blank
+/// lines and lines starting with `#` are skipped.
+pub fn parse_globals_file(content: &str) -> Result<Vec<String>, String> {
+  if content.trim_start().starts_with('[') {
+    return serde_json::from_str(&strip_jsonc(content)).map_err(|e| e.to_string());
+  }
+
+  Ok(
+    content
+      .lines()
+      .map(str::trim)
+      .filter(|line| !line.is_empty() && !line.starts_with('#'))
+      .map(str::to_string)
+      .collect(),
+  )
+}
+
+/// Deserializes the `name` section of `settings`. Keys whose values don't fit are dropped with a
+/// warning, so one bad value doesn't reset the whole section.
+fn section<T: DeserializeOwned + Default>(settings: &Value, name: &str) -> T {
+  let Some(Value::Object(section)) = settings.get(name) else {
+    return T::default();
+  };
+
+  if let Ok(parsed) = serde_json::from_value(Value::Object(section.clone())) {
+    return parsed;
+  }
+
+  let valid = section
+    .iter()
+    .filter(|(key, value)| {
+      let single = Map::from_iter([(key.to_string(), (*value).clone())]);
+      serde_json::from_value::<T>(Value::Object(single))
+        .inspect_err(|e| warn!("ignoring invalid setting {name}.{key}: {e}"))
+        .is_ok()
+    })
+    .map(|(key, value)| (key.clone(), value.clone()))
+    .collect::<Map<_, _>>();
+
+  serde_json::from_value(Value::Object(valid)).unwrap_or_default()
+}
+
+/// Accepts `false` for `completion.postfix` as the disabled trigger, which the server spells as an
+/// empty string; `true` keeps the default `@`.
+fn deserialize_postfix<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<String, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum Postfix {
+    Enabled(bool),
+    Trigger(String),
+  }
+
+  Ok(match Postfix::deserialize(deserializer)? {
+    Postfix::Enabled(true) => "@".to_string(),
+    Postfix::Enabled(false) => String::new(),
+    Postfix::Trigger(trigger) => trigger.trim().to_string(),
+  })
+}
+
+/// Accepts the `completion.callSnippet` modes of lua-language-server next to a plain boolean, so
+/// settings carried over from it keep working. emmylua_ls only knows on and off, so `"both"` and
+/// `"replace"` both turn snippets on.
+fn deserialize_call_snippet<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<bool, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum CallSnippet {
+    Enabled(bool),
+    Mode(String),
+  }
+
+  match CallSnippet::deserialize(deserializer)? {
+    CallSnippet::Enabled(enabled) => Ok(enabled),
+    CallSnippet::Mode(mode) => match mode.trim().to_ascii_lowercase().as_str() {
+      "disable" => Ok(false),
+      "both" | "replace" => Ok(true),
+      _ => Err(serde::de::Error::custom(format!(
+        "unknown mode '{mode}', expected true, false, \"disable\", \"both\" or \"replace\""
+      ))),
+    },
+  }
+}
+
+/// Accepts `completion.autoRequireFunction` as a single function name or a list of them.
+fn deserialize_require_functions<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum RequireFunctions {
+    One(String),
+    Many(Vec<String>),
+  }
+
+  Ok(match RequireFunctions::deserialize(deserializer)? {
+    RequireFunctions::One(function) => vec![function],
+    RequireFunctions::Many(functions) => functions,
+  })
+}
+
+fn serialize_require_function<S: serde::Serializer>(
+  functions: &[String],
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  serializer.serialize_str(functions.first().map_or("require", String::as_str))
+}
+
+/// Skips `runtime.frameworkVersions` entries that aren't strings, which would make the server
+/// reject the whole list.
+fn deserialize_framework_versions<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<Vec<String>, D::Error> {
+  Ok(
+    Vec::<Value>::deserialize(deserializer)?
+      .into_iter()
+      .filter_map(|entry| match entry {
+        Value::String(entry) => Some(entry),
+        other => {
+          warn!("ignoring runtime.frameworkVersions entry {other}: expected a string");
+          None
+        }
+      })
+      .collect(),
+  )
+}
+
+/// Reads a byte count given either as a number or as a string with a `B`, `KB`, `MB` or `GB`
+/// suffix, e.g. `"500KB"`. Suffixes are binary, so `"1KB"` is 1024 bytes.
+fn deserialize_file_size<'de, D: serde::Deserializer<'de>>(
+  deserializer: D,
+) -> Result<i64, D::Error> {
+  #[derive(Deserialize)]
+  #[serde(untagged)]
+  enum FileSize {
+    Bytes(i64),
+    Text(String),
+  }
+
+  let text = match FileSize::deserialize(deserializer)? {
+    FileSize::Bytes(bytes) => return Ok(bytes),
+    FileSize::Text(text) => text,
+  };
+
+  let upper = text.trim().to_ascii_uppercase();
+  let digits = upper
+    .find(|c: char| !c.is_ascii_digit())
+    .unwrap_or(upper.len());
+  let (number, suffix) = upper.split_at(digits);
+  let multiplier = match suffix.trim() {
+    "" | "B" => 1,
+    "KB" | "K" => 1024,
+    "MB" | "M" => 1024 * 1024,
+    "GB" | "G" => 1024 * 1024 * 1024,
+    _ => 0,
+  };
+
+  number
+    .parse::<i64>()
+    .ok()
+    .filter(|_| multiplier > 0)
+    .and_then(|number| number.checked_mul(multiplier))
+    .ok_or_else(|| {
+      serde::de::Error::custom(format!(
+        "'{text}' is not a size, expected e.g. 1048576, \"500KB\" or \"2MB\""
+      ))
+    })
+}
+
+fn clamp_interval(name: &str, milliseconds: u64) -> u64 {
+  if milliseconds < MIN_INTERVAL_MS {
+    warn!("{name} {milliseconds}ms is too short, using {MIN_INTERVAL_MS}ms");
+    return MIN_INTERVAL_MS;
+  }
+  milliseconds
+}
+
+/// Maps an auto-require naming convention onto the kebab-case names emmylua_ls accepts, so
+/// `camelCase`, `camel_case` and `camel-case` all work.
+fn normalize_naming_convention(convention: &str) -> Option<&'static str> {
+  let key = convention
+    .chars()
+    .filter(char::is_ascii_alphanumeric)
+    .collect::<String>()
+    .to_ascii_lowercase();
+
+  match key.as_str() {
+    "keep" => Some("keep"),
+    "keepclass" => Some("keep-class"),
+    "camelcase" => Some("camel-case"),
+    "snakecase" => Some("snake-case"),
+    "pascalcase" => Some("pascal-case"),
+    _ => None,
+  }
+}
+
+/// Maps a diagnostic severity onto `error`, `warning`, `information` or `hint`, accepting any case
+/// and the short forms `warn` and `info`.
+fn normalize_severity(severity: &str) -> Option<&'static str> {
+  match severity.trim().to_ascii_lowercase().as_str() {
+    "error" => Some("error"),
+    "warning" | "warn" => Some("warning"),
+    "information" | "info" => Some("information"),
+    "hint" => Some("hint"),
+    _ => None,
+  }
+}
+
+/// Maps a `runtime.version` value onto the spelling emmylua_ls accepts, tolerating common
+/// variants such as `Lua54`, `lua 5.4` or `5.4`.
+pub fn normalize_runtime_version(version: &str) -> Option<&'static str> {
+  let key = version
+    .chars()
+    .filter(char::is_ascii_alphanumeric)
+    .collect::<String>()
+    .to_ascii_lowercase();
+
+  match key.strip_prefix("lua").unwrap_or(&key) {
+    "51" => Some("Lua5.1"),
+    "52" => Some("Lua5.2"),
+    "53" => Some("Lua5.3"),
+    "54" => Some("Lua5.4"),
+    "55" => Some("Lua5.5"),
+    "jit" => Some("LuaJIT"),
+    "latest" => Some("LuaLatest"),
+    _ => None,
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Workspace {
+  pub library: Vec<Value>,
+  pub ignore_dir: Vec<String>,
+  pub ignore_globs: Vec<String>,
+  pub workspace_roots: Vec<String>,
+  /// Use the project root as the workspace root when `workspace_roots` is empty. Read by the
+  /// extension, not sent to the server.
+  #[serde(skip_serializing)]
+  pub auto_detect_root: bool,
+  pub module_map: Vec<Value>,
+  pub encoding: String,
+  /// Size limit in bytes, also accepted as a string such as `"2MB"`.
+  #[serde(deserialize_with = "deserialize_file_size")]
+  pub preload_file_size: i64,
+  pub enable_reindex: bool,
+  /// Delay in milliseconds before reindexing after a change, only sent with `enable_reindex`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reindex_duration: Option<u64>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Workspace {
+  fn default() -> Self {
+    Self {
+      library: Vec::new(),
+      ignore_dir: Vec::new(),
+      ignore_globs: Vec::new(),
+      workspace_roots: Vec::new(),
+      auto_detect_root: true,
+      module_map: Vec::new(),
+      encoding: "utf-8".to_string(),
+      preload_file_size: 0,
+      enable_reindex: false,
+      reindex_duration: Some(5000),
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Completion {
+  pub enable: bool,
+  /// Whether function completions insert a call snippet with the parameters.
+  #[serde(deserialize_with = "deserialize_call_snippet")]
+  pub call_snippet: bool,
+  pub auto_require: bool,
+  /// Functions used to load modules, given as a string or a list. The server only takes one, so
+  /// this is reduced to the first entry and the others become `runtime.requireLikeFunction`.
+  #[serde(
+    deserialize_with = "deserialize_require_functions",
+    serialize_with = "serialize_require_function"
+  )]
+  pub auto_require_function: Vec<String>,
+  pub auto_require_naming_convention: String,
+  pub auto_require_separator: String,
+  pub base_function_includes_name: bool,
+  /// Postfix completion trigger character, empty when postfix completion is disabled.
+  #[serde(deserialize_with = "deserialize_postfix")]
+  pub postfix: String,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Completion {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      call_snippet: false,
+      auto_require: true,
+      auto_require_function: vec!["require".to_string()],
+      auto_require_naming_convention: "keep".to_string(),
+      auto_require_separator: ".".to_string(),
+      base_function_includes_name: true,
+      postfix: "@".to_string(),
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Diagnostics {
+  pub enable: bool,
+  pub globals: Vec<String>,
+  pub globals_regex: Vec<String>,
+  pub disable: Vec<String>,
+  pub enables: Vec<String>,
+  pub severity: Map<String, Value>,
+  /// Delay in milliseconds before diagnostics are refreshed.
+  pub diagnostic_interval: u64,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Diagnostics {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      globals: Vec::new(),
+      globals_regex: Vec::new(),
+      disable: Vec::new(),
+      enables: Vec::new(),
+      severity: Map::new(),
+      diagnostic_interval: 500,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Hint {
+  pub enable: bool,
+  pub param_hint: bool,
+  pub local_hint: bool,
+  pub index_hint: bool,
+  pub override_hint: bool,
+  pub meta_call_hint: bool,
+  pub enum_param_hint: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Hint {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      param_hint: true,
+      local_hint: true,
+      index_hint: true,
+      override_hint: true,
+      meta_call_hint: true,
+      enum_param_hint: false,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Runtime {
+  pub version: String,
+  pub extensions: Vec<String>,
+  pub require_like_function: Vec<String>,
+  pub require_pattern: Vec<String>,
+  pub nonstandard_symbol: Vec<String>,
+  /// Framework and version pairs such as `love2d@11.4`.
+  #[serde(deserialize_with = "deserialize_framework_versions")]
+  pub framework_versions: Vec<String>,
+  pub special: Map<String, Value>,
+  pub class_default_call: ClassDefaultCall,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Runtime {
+  fn default() -> Self {
+    Self {
+      version: "LuaLatest".to_string(),
+      extensions: Vec::new(),
+      require_like_function: Vec::new(),
+      require_pattern: Vec::new(),
+      nonstandard_symbol: Vec::new(),
+      framework_versions: Vec::new(),
+      special: Map::new(),
+      class_default_call: ClassDefaultCall::default(),
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ClassDefaultCall {
+  pub function_name: String,
+  pub force_non_colon: bool,
+  pub force_return_self: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Hover {
+  pub enable: bool,
+  /// Hover detail level from 1 to 255. Left out when unset so the server's own default applies
+  /// rather than `null`.
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub custom_detail: Option<u64>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Hover {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      custom_detail: None,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Format {
+  pub use_diff: bool,
+  pub external_tool: Option<ExternalTool>,
+  pub external_tool_range_format: Option<ExternalTool>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+/// External formatter such as StyLua. `args` may use the server's placeholders, e.g. `${file}`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalTool {
+  #[serde(alias = "command")]
+  pub program: String,
+  pub args: Vec<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub timeout: Option<u64>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Doc {
+  pub syntax: String,
+  pub known_tags: Vec<String>,
+  pub private_name: Vec<String>,
+  pub rst_default_role: Option<String>,
+  pub rst_primary_domain: Option<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Doc {
+  fn default() -> Self {
+    Self {
+      syntax: "md".to_string(),
+      known_tags: Vec::new(),
+      private_name: Vec::new(),
+      rst_default_role: None,
+      rst_primary_domain: None,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CodeLens {
+  pub enable: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for CodeLens {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct SemanticTokens {
+  pub enable: bool,
+  pub render_documentation_markup: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for SemanticTokens {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      render_documentation_markup: false,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Signature {
+  pub detail_signature_helper: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Signature {
+  fn default() -> Self {
+    Self {
+      detail_signature_helper: true,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct References {
+  pub enable: bool,
+  pub fuzzy_search: bool,
+  pub short_string_search: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for References {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      fuzzy_search: true,
+      short_string_search: false,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DocumentColor {
+  pub enable: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for DocumentColor {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InlineValues {
+  pub enable: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for InlineValues {
+  fn default() -> Self {
+    Self {
+      enable: true,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct CodeAction {
+  pub insert_space: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Strict {
+  pub array_index: bool,
+  pub doc_base_const_match_base_type: bool,
+  pub meta_override_file_define: bool,
+  pub require_path: bool,
+  pub type_call: bool,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+impl Default for Strict {
+  fn default() -> Self {
+    Self {
+      array_index: true,
+      doc_base_const_match_base_type: false,
+      meta_override_file_define: true,
+      require_path: false,
+      type_call: false,
+      extra: Map::new(),
+    }
+  }
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Resource {
+  pub paths: Vec<String>,
+  #[serde(flatten)]
+  pub extra: Map<String, Value>,
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  /// What the hand-written `json!` block sent for empty settings before the typed structs.
+  fn baseline_defaults() -> Value {
+    json!({
+      "workspace": {
+        "library": [],
+        "ignoreDir": [],
+        "ignoreGlobs": [],
+        "workspaceRoots": [],
+        "moduleMap": [],
+        "encoding": "utf-8",
+        "preloadFileSize": 0,
+        "enableReindex": false,
+        "reindexDuration": 5000,
+      },
+      "completion": {
+        "enable": true,
+        "callSnippet": false,
+        "autoRequire": true,
+        "autoRequireFunction": "require",
+        "autoRequireNamingConvention": "keep",
+        "autoRequireSeparator": ".",
+        "baseFunctionIncludesName": true,
+        "postfix": "@",
+      },
+      "diagnostics": {
+        "enable": true,
+        "globals": [],
+        "globalsRegex": [],
+        "disable": [],
+        "enables": [],
+        "severity": {},
+        "diagnosticInterval": 500,
+      },
+      "hint": {
+        "enable": true,
+        "paramHint": true,
+        "localHint": true,
+        "indexHint": true,
+        "overrideHint": true,
+        "metaCallHint": true,
+        "enumParamHint": false,
+      },
+      "runtime": {
+        "version": "LuaLatest",
+        "extensions": [],
+        "requireLikeFunction": [],
+        "requirePattern": [],
+        "nonstandardSymbol": [],
+        "frameworkVersions": [],
+        "special": {},
+        "classDefaultCall": {
+          "functionName": "",
+          "forceNonColon": false,
+          "forceReturnSelf": false,
+        },
+      },
+      "hover": {
+        "enable": true,
+        "customDetail": null,
+      },
+      "format": {
+        "useDiff": false,
+        "externalTool": null,
+        "externalToolRangeFormat": null,
+      },
+      "doc": {
+        "syntax": "md",
+        "knownTags": [],
+        "privateName": [],
+        "rstDefaultRole": null,
+        "rstPrimaryDomain": null,
+      },
+      "codeLens": { "enable": true },
+      "semanticTokens": {
+        "enable": true,
+        "renderDocumentationMarkup": false,
+      },
+      "signature": { "detailSignatureHelper": true },
+      "references": {
+        "enable": true,
+        "fuzzySearch": true,
+        "shortStringSearch": false,
+      },
+      "documentColor": { "enable": true },
+      "inlineValues": { "enable": true },
+      "codeAction": { "insertSpace": false },
+      "strict": {
+        "arrayIndex": true,
+        "docBaseConstMatchBaseType": false,
+        "metaOverrideFileDefine": true,
+        "requirePath": false,
+        "typeCall": false,
+      },
+      "resource": { "paths": [] },
+    })
+  }
+
+  fn configuration(settings: Value) -> Value {
+    serde_json::to_value(EmmyConfig::from_settings(&settings)).unwrap()
+  }
+
+  #[test]
+  fn empty_settings_match_baseline() {
+    let mut expected = baseline_defaults();
+    // Only sent while reindexing is enabled
+    expected["workspace"]
+      .as_object_mut()
+      .unwrap()
+      .remove("reindexDuration");
+    // Only sent when set
+    expected["hover"]
+      .as_object_mut()
+      .unwrap()
+      .remove("customDetail");
+
+    assert_eq!(configuration(json!({})), expected);
+  }
+
+  #[test]
+  fn hover_custom_detail_is_clamped() {
+    let detail = |value: Value| configuration(json!({ "hover": { "customDetail": value } }));
+    assert_eq!(detail(json!(0))["hover"]["customDetail"], 1);
+    assert_eq!(detail(json!(1000))["hover"]["customDetail"], 255);
+    assert_eq!(detail(json!(8))["hover"]["customDetail"], 8);
+    // Unset or unusable, it is left out so the server's own default applies
+    assert!(detail(json!("max"))["hover"].get("customDetail").is_none());
+    assert!(
+      configuration(json!({}))["hover"]
+        .get("customDetail")
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn configuration_round_trips() {
+    let first = configuration(json!({}));
+    assert_eq!(configuration(first.clone()), first);
+  }
+
+  #[test]
+  fn completion_postfix() {
+    let postfix = |value: Value| {
+      configuration(json!({ "completion": { "postfix": value } }))["completion"]["postfix"].clone()
+    };
+    assert_eq!(configuration(json!({}))["completion"]["postfix"], "@");
+    assert_eq!(postfix(json!("!")), "!");
+    assert_eq!(postfix(json!(true)), "@");
+    assert_eq!(postfix(json!(false)), "");
+    assert_eq!(postfix(json!("->")), "@");
+  }
+
+  #[test]
+  fn short_intervals_are_clamped() {
+    assert_eq!(
+      clamp_interval("diagnostics.diagnosticInterval", 0),
+      MIN_INTERVAL_MS
+    );
+    assert_eq!(
+      clamp_interval("diagnostics.diagnosticInterval", 99),
+      MIN_INTERVAL_MS
+    );
+    assert_eq!(clamp_interval("diagnostics.diagnosticInterval", 100), 100);
+    assert_eq!(clamp_interval("diagnostics.diagnosticInterval", 2000), 2000);
+
+    let config = configuration(json!({
+      "workspace": { "enableReindex": true, "reindexDuration": 10 },
+      "diagnostics": { "diagnosticInterval": 1 },
+    }));
+    assert_eq!(config["workspace"]["reindexDuration"], MIN_INTERVAL_MS);
+    assert_eq!(config["diagnostics"]["diagnosticInterval"], MIN_INTERVAL_MS);
+  }
+
+  #[test]
+  fn reindex_duration_follows_enable_reindex() {
+    let config = configuration(json!({
+      "workspace": { "enableReindex": false, "reindexDuration": 8000 },
+    }));
+    assert!(config["workspace"].get("reindexDuration").is_none());
+
+    let config = configuration(json!({
+      "workspace": { "enableReindex": true, "reindexDuration": 8000 },
+    }));
+    assert_eq!(config["workspace"]["reindexDuration"], 8000);
+
+    let config = configuration(json!({
+      "workspace": { "enableReindex": true, "reindexDuration": 50 },
+    }));
+    assert_eq!(config["workspace"]["reindexDuration"], MIN_INTERVAL_MS);
+  }
+
+  #[test]
+  fn naming_conventions() {
+    for (convention, expected) in [
+      ("keep", "keep"),
+      ("keep-class", "keep-class"),
+      ("keepClass", "keep-class"),
+      ("camelCase", "camel-case"),
+      ("camel_case", "camel-case"),
+      ("snake-case", "snake-case"),
+      ("SnakeCase", "snake-case"),
+      ("pascal-case", "pascal-case"),
+      ("PascalCase", "pascal-case"),
+    ] {
+      assert_eq!(
+        normalize_naming_convention(convention),
+        Some(expected),
+        "{convention}"
+      );
+    }
+    assert_eq!(normalize_naming_convention("kebab-case"), None);
+
+    let config = configuration(json!({
+      "completion": { "autoRequireNamingConvention": "kebab-case" },
+    }));
+    assert_eq!(config["completion"]["autoRequireNamingConvention"], "keep");
+  }
+
+  #[test]
+  fn auto_require_separator_falls_back_to_dot() {
+    let separator = |value: Value| {
+      configuration(json!({ "completion": { "autoRequireSeparator": value } }))["completion"]
+        ["autoRequireSeparator"]
+        .clone()
+    };
+    assert_eq!(separator(json!("/")), "/");
+    assert_eq!(separator(json!(" / ")), "/");
+    assert_eq!(separator(json!(".")), ".");
+    assert_eq!(separator(json!("::")), ".");
+    assert_eq!(separator(json!("")), ".");
+  }
+
+  #[test]
+  fn call_snippet_modes() {
+    let call_snippet = |value: Value| {
+      configuration(json!({ "completion": { "callSnippet": value } }))["completion"]["callSnippet"]
+        .clone()
+    };
+    assert_eq!(call_snippet(json!(true)), true);
+    assert_eq!(call_snippet(json!(false)), false);
+    assert_eq!(call_snippet(json!("Disable")), false);
+    assert_eq!(call_snippet(json!("both")), true);
+    assert_eq!(call_snippet(json!("replace")), true);
+    // Unknown modes keep the default
+    assert_eq!(call_snippet(json!("sometimes")), false);
+  }
+
+  #[test]
+  fn auto_require_function_forms() {
+    let config = configuration(json!({ "completion": { "autoRequireFunction": "import" } }));
+    assert_eq!(config["completion"]["autoRequireFunction"], "import");
+    assert_eq!(config["runtime"]["requireLikeFunction"], json!([]));
+
+    let config = configuration(json!({
+      "completion": { "autoRequireFunction": ["import", " load ", "import", ""] },
+    }));
+    assert_eq!(config["completion"]["autoRequireFunction"], "import");
+    assert_eq!(config["runtime"]["requireLikeFunction"], json!(["load"]));
+
+    let config = configuration(json!({ "completion": { "autoRequireFunction": [] } }));
+    assert_eq!(config["completion"]["autoRequireFunction"], "require");
+  }
+
+  #[test]
+  fn explicit_strict_keys_override_the_preset() {
+    let mut settings = json!({ "strict": { "preset": "strict", "requirePath": false } });
+    expand_strict_preset(&mut settings);
+    assert_eq!(
+      configuration(settings)["strict"],
+      json!({
+        "arrayIndex": true,
+        "docBaseConstMatchBaseType": true,
+        "metaOverrideFileDefine": true,
+        "requirePath": false,
+        "typeCall": true,
+      })
+    );
+
+    let mut settings = json!({ "strict": { "preset": "off", "typeCall": true } });
+    expand_strict_preset(&mut settings);
+    assert_eq!(settings["strict"]["arrayIndex"], false);
+    assert_eq!(settings["strict"]["typeCall"], true);
+    assert!(settings["strict"].get("preset").is_none());
+
+    let mut settings = json!({ "strict": { "preset": "Relaxed" } });
+    expand_strict_preset(&mut settings);
+    assert_eq!(settings["strict"], configuration(json!({}))["strict"]);
+  }
+
+  #[test]
+  fn version_ordering() {
+    assert!(version_at_least("0.9.1", "0.9.0"));
+    assert!(version_at_least("0.9.0", "0.9.0"));
+    assert!(!version_at_least("0.8.9", "0.9.0"));
+    assert!(version_at_least("0.10.0", "0.9.0"));
+    assert!(version_at_least("1.0.0", "0.99.99"));
+
+    // A pre-release comes before its release, but after the release before it
+    assert!(!version_at_least("0.9.0-beta", "0.9.0"));
+    assert!(version_at_least("0.9.0-rc1", "0.9.0-beta"));
+    assert!(version_at_least("0.9.0", "0.9.0-beta"));
+    assert!(version_at_least("0.9.1-beta", "0.9.0"));
+
+    assert!(version_at_least("v0.9.0", "0.9.0"));
+    assert!(!version_at_least("v0.8.0", "v0.9.0"));
+
+    // Missing components count as zero
+    assert!(version_at_least("1", "0.9.0"));
+    assert!(version_at_least("0.9", "0.9.0"));
+    assert!(!version_at_least("0.8", "0.9.0"));
+
+    // Tags that aren't versions are never held back
+    assert!(version_at_least("nightly", "0.9.0"));
+    assert!(version_at_least("", "0.9.0"));
+    assert!(version_at_least("0.9.0", "latest"));
+  }
+
+  #[test]
+  fn parse_release_tags() {
+    assert_eq!(parse_version("0.9.1"), Some((0, 9, 1)));
+    assert_eq!(parse_version(" v0.10.0-beta "), Some((0, 10, 0)));
+    assert_eq!(parse_version("1.2"), Some((1, 2, 0)));
+    assert_eq!(parse_version("1.2.3.4"), Some((1, 2, 3)));
+    assert_eq!(parse_version("nightly"), None);
+  }
+}
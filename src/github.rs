@@ -0,0 +1,225 @@
+use crate::Channel;
+use zed_extension_api::{
+  self as zed, Result,
+  http_client::{HttpMethod, HttpRequest, RedirectPolicy},
+  serde_json::{self, Value},
+};
+
+/// Release metadata, either from the extension API or from the GitHub REST API.
+pub struct Release {
+  pub version: String,
+  pub assets: Vec<ReleaseAsset>,
+}
+
+pub struct ReleaseAsset {
+  pub name: String,
+  pub download_url: String,
+  /// Size in bytes, only known when the release comes from the GitHub API.
+  pub size: Option<u64>,
+}
+
+impl Release {
+  /// Rejects releases whose tag can't be used as a version: empty, or containing whitespace or
+  /// control characters that would garble the update lock.
+  fn validated(self) -> Result<Self> {
+    let version = &self.version;
+    if version.is_empty() || version.len() > 128 {
+      return Err(format!("Release has an invalid tag '{version}'"));
+    }
+    if version.chars().any(|c| c.is_whitespace() || c.is_control()) {
+      return Err(format!(
+        "Release tag {version:?} contains whitespace or control characters"
+      ));
+    }
+    Ok(self)
+  }
+}
+
+impl From<zed::GithubRelease> for Release {
+  fn from(release: zed::GithubRelease) -> Self {
+    Self {
+      version: release.version,
+      assets: release
+        .assets
+        .into_iter()
+        .map(|asset| ReleaseAsset {
+          name: asset.name,
+          download_url: asset.download_url,
+          size: None,
+        })
+        .collect(),
+    }
+  }
+}
+
+/// Latest release of `repository` on `channel`. With a token, or for the beta and nightly
+/// channels, the GitHub API is queried directly: the extension API can't pick out release
+/// candidates, and with pre-releases allowed it also returns a newer stable release.
+pub fn latest_release(repository: &str, channel: Channel, token: Option<&str>) -> Result<Release> {
+  if token.is_none() && channel == Channel::Stable {
+    return zed::latest_github_release(
+      repository,
+      zed::GithubReleaseOptions {
+        require_assets: true,
+        pre_release: false,
+      },
+    )
+    .map(Release::from)
+    .and_then(Release::validated);
+  }
+
+  let url = format!("https://api.github.com/repos/{repository}/releases?per_page=30");
+  let releases = get_json(&url, token)?;
+  releases
+    .as_array()
+    .into_iter()
+    .flatten()
+    .filter(|release| release.get("draft").and_then(|v| v.as_bool()) != Some(true))
+    .filter(|release| {
+      let tag = release.get("tag_name").and_then(|v| v.as_str());
+      let prerelease = release.get("prerelease").and_then(|v| v.as_bool());
+      tag
+        .zip(prerelease)
+        .is_some_and(|(tag, prerelease)| on_channel(tag, prerelease, channel))
+    })
+    .filter_map(parse_release)
+    .find(|release| !release.assets.is_empty())
+    .ok_or_else(|| format!("No release with assets found in {repository}"))
+    .and_then(Release::validated)
+}
+
+/// Whether a release tagged `tag` belongs to `channel`. GitHub lists releases newest first, so the
+/// first match is the channel's latest release. Nightly takes the most recent pre-release of any
+/// kind, so it never falls back to a stable release.
+fn on_channel(tag: &str, prerelease: bool, channel: Channel) -> bool {
+  match channel {
+    Channel::Stable => !prerelease,
+    Channel::Beta => prerelease && tag.to_ascii_lowercase().contains("-rc"),
+    Channel::Nightly => prerelease,
+  }
+}
+
+/// Release of `repository` tagged `tag`.
+pub fn release_by_tag(repository: &str, tag: &str, token: Option<&str>) -> Result<Release> {
+  if token.is_none() {
+    return zed::github_release_by_tag_name(repository, tag)
+      .map(Release::from)
+      .and_then(Release::validated);
+  }
+
+  let url = format!("https://api.github.com/repos/{repository}/releases/tags/{tag}");
+  let release = get_json(&url, token)?;
+  parse_release(&release)
+    .ok_or_else(|| format!("Invalid release data for tag {tag}"))
+    .and_then(Release::validated)
+}
+
+/// Whether a failed release lookup was turned away by GitHub's rate limit rather than failing to
+/// reach it.
+pub fn is_rate_limited(error: &str) -> bool {
+  let error = error.to_ascii_lowercase();
+  error.contains("rate limit exceeded") || error.contains("secondary rate limit")
+}
+
+/// Whether a failed release lookup was answered with GitHub's "Not Found".
+pub fn is_not_found(error: &str) -> bool {
+  let error = error.to_ascii_lowercase();
+  error.contains("not found") || error.contains("404")
+}
+
+/// What the user can do about a failed release lookup.
+pub fn lookup_hint(error: &str, has_token: bool) -> &'static str {
+  match (is_rate_limited(error), has_token) {
+    (true, false) => {
+      "GitHub limits unauthenticated API requests; set server.githubToken or GITHUB_TOKEN to \
+       raise the limit. Until then an already installed emmylua_ls is used if there is one"
+    }
+    (true, true) => "GitHub's API rate limit was reached even with the token; try again later",
+    (false, _) => "Check the network connection and Zed's proxy setting",
+  }
+}
+
+fn get_json(url: &str, token: Option<&str>) -> Result<Value> {
+  let mut request = HttpRequest::builder()
+    .method(HttpMethod::Get)
+    .url(url)
+    .header("Accept", "application/vnd.github+json")
+    .header("User-Agent", "zed-emmylua-ls")
+    .redirect_policy(RedirectPolicy::FollowAll);
+  if let Some(token) = token {
+    request = request.header("Authorization", format!("Bearer {token}"));
+  }
+  let request = request.build()?;
+
+  // Errors never include the request itself so the token cannot leak into messages
+  let response = request
+    .fetch()
+    .map_err(|e| format!("GitHub API request to {url} failed: {e}"))?;
+  let value: Value = serde_json::from_slice(&response.body)
+    .map_err(|e| format!("Invalid response from {url}: {e}"))?;
+
+  if let Some(message) = value.get("message").and_then(|v| v.as_str()) {
+    return Err(format!("GitHub API request to {url} failed: {message}"));
+  }
+
+  Ok(value)
+}
+
+fn parse_release(release: &Value) -> Option<Release> {
+  let assets = release
+    .get("assets")?
+    .as_array()?
+    .iter()
+    .filter_map(|asset| {
+      Some(ReleaseAsset {
+        name: asset.get("name")?.as_str()?.to_string(),
+        download_url: asset.get("browser_download_url")?.as_str()?.to_string(),
+        size: asset.get("size").and_then(|v| v.as_u64()),
+      })
+    })
+    .collect();
+
+  Some(Release {
+    version: release.get("tag_name")?.as_str()?.to_string(),
+    assets,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn releases_per_channel() {
+    assert!(on_channel("0.9.1", false, Channel::Stable));
+    assert!(!on_channel("0.10.0-rc1", true, Channel::Stable));
+
+    assert!(on_channel("0.10.0-rc1", true, Channel::Beta));
+    assert!(on_channel("0.10.0-RC.2", true, Channel::Beta));
+    assert!(!on_channel("0.10.0-nightly", true, Channel::Beta));
+    assert!(!on_channel("0.9.1", false, Channel::Beta));
+
+    assert!(on_channel("0.10.0-nightly", true, Channel::Nightly));
+    assert!(on_channel("0.10.0-rc1", true, Channel::Nightly));
+    assert!(!on_channel("0.9.1", false, Channel::Nightly));
+  }
+
+  #[test]
+  fn lookup_errors_are_classified() {
+    let rate_limited = "GitHub API request to https://api.github.com/repos/o/r/releases failed: \
+                        API rate limit exceeded for 127.0.0.1.";
+    assert!(is_rate_limited(rate_limited));
+    assert!(!is_not_found(rate_limited));
+
+    let missing = "GitHub API request to https://api.github.com/repos/o/r/releases/tags/9.9.9 \
+                   failed: Not Found";
+    assert!(is_not_found(missing));
+    assert!(!is_rate_limited(missing));
+
+    let offline = "GitHub API request to https://api.github.com/repos/o/r/releases/tags/0.9.1 \
+                   failed: error sending request: dns error";
+    assert!(!is_rate_limited(offline));
+    assert!(!is_not_found(offline));
+    assert!(!is_rate_limited("tag 0.9.403 has no assets"));
+  }
+}
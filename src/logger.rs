@@ -0,0 +1,110 @@
+//! Extension log, appended to `logs/extension.log` in the install directory and mirrored to
+//! stderr, which ends up in Zed's log.
+//!
+//! Warnings and errors are always written. More detailed messages follow `server.logLevel`, so
+//! the log stays quiet unless asked for.
+
+use std::fmt::Arguments;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+
+use crate::LogLevel;
+
+/// Size after which the log is moved aside to `extension.log.old` on startup.
+const MAX_LOG_SIZE: u64 = 1024 * 1024;
+
+struct Logger {
+  level: LogLevel,
+  file: Option<PathBuf>,
+}
+
+static LOGGER: Mutex<Logger> = Mutex::new(Logger {
+  level: LogLevel::Warn,
+  file: None,
+});
+
+/// Starts writing to `dir/extension.log` at `level`. Until then messages only go to stderr.
+pub fn init(level: LogLevel, dir: &Path) {
+  let file = dir.join("extension.log");
+  if std::fs::create_dir_all(dir).is_ok()
+    && std::fs::metadata(&file).is_ok_and(|metadata| metadata.len() > MAX_LOG_SIZE)
+  {
+    let _ = std::fs::rename(&file, file.with_extension("log.old"));
+  }
+
+  let mut logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+  logger.level = level.min(LogLevel::Warn);
+  logger.file = Some(file);
+}
+
+pub fn log(level: LogLevel, message: Arguments) {
+  let logger = LOGGER.lock().unwrap_or_else(PoisonError::into_inner);
+  if level < logger.level {
+    return;
+  }
+
+  eprintln!("emmylua: {message}");
+
+  if let Some(file) = &logger.file
+    && let Ok(mut output) = std::fs::OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(file)
+  {
+    let _ = writeln!(
+      output,
+      "{} {:<5} {message}",
+      format_timestamp(crate::unix_timestamp()),
+      level.as_str().to_ascii_uppercase()
+    );
+  }
+}
+
+/// Formats seconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS` in UTC.
+fn format_timestamp(timestamp: u64) -> String {
+  let (days, seconds) = (timestamp / 86_400, timestamp % 86_400);
+
+  // Civil date from days since 1970-01-01, see http://howardhinnant.github.io/date_algorithms.html
+  let z = days as i64 + 719_468;
+  let era = z.div_euclid(146_097);
+  let day_of_era = z.rem_euclid(146_097);
+  let year_of_era =
+    (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+  let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+  let mp = (5 * day_of_year + 2) / 153;
+  let day = day_of_year - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+  format!(
+    "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02}",
+    seconds / 3600,
+    seconds / 60 % 60,
+    seconds % 60
+  )
+}
+
+macro_rules! error {
+  ($($arg:tt)*) => {
+    $crate::logger::log($crate::LogLevel::Error, format_args!($($arg)*))
+  };
+}
+
+macro_rules! warn {
+  ($($arg:tt)*) => {
+    $crate::logger::log($crate::LogLevel::Warn, format_args!($($arg)*))
+  };
+}
+
+macro_rules! info {
+  ($($arg:tt)*) => {
+    $crate::logger::log($crate::LogLevel::Info, format_args!($($arg)*))
+  };
+}
+
+macro_rules! debug {
+  ($($arg:tt)*) => {
+    $crate::logger::log($crate::LogLevel::Debug, format_args!($($arg)*))
+  };
+}